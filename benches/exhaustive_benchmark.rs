@@ -32,6 +32,10 @@ pub fn main() -> Result<()>
         .context("Failed to create test inputs")?;
 
     c.bench_function("exhaustive", wrap!(run_ours(filename)));
+    c.bench_function(
+        "exhaustive-parallel",
+        wrap!(run_ours_parallel(filename, 8)),
+    );
 
     delete_test_data_file(filename)
         .context("Failed to clean up test input file")?;
@@ -116,6 +116,18 @@ pub fn run_ours(test_data_filename: &str) -> Result<isize>
     run(binary, no_args, input)
 }
 
+/// Runs the compiled binary with `-j <threads>`, exercising the
+/// parallel chunked reduction path instead of the single-threaded
+/// default.
+pub fn run_ours_parallel(test_data_filename: &str, threads: u32) -> Result<isize>
+{
+    let binary = env!("CARGO_BIN_EXE_stdio-utils");
+    let input = fopen(test_data_filename)?;
+    let threads = threads.to_string();
+
+    run(binary, &["-j", threads.as_str()], input)
+}
+
 pub fn run<A, S, I>(cmd: &str, args: A, input: I) -> Result<isize>
 where
     A: IntoIterator<Item = S>,
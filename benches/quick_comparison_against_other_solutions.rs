@@ -40,6 +40,10 @@ fn main() -> Result<()>
         run_ours(filename).context("Failed to compute expected test result")?;
 
     c.bench_function(env!("CARGO_PKG_NAME"), wrap!(run_ours(filename), exp));
+    c.bench_function(
+        &format!("{}-parallel", env!("CARGO_PKG_NAME")),
+        wrap!(run_ours_parallel(filename, 4), exp),
+    );
     c.bench_function("python", wrap!(run_python_variant(filename), exp));
     c.bench_function("awk", wrap!(run_awk_variant(filename), exp));
     c.bench_function("paste|bc", wrap!(run_bc_variant(filename), exp));
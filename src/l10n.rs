@@ -0,0 +1,295 @@
+// Copyright 2020 Andreas Waidler
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included
+// in all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Localizable diagnostics: [`Error`] values are rendered through an
+//! ordered chain of Fluent-like message bundles that falls back to the
+//! next requested locale -- and finally to a built-in English default
+//! -- whenever a bundle is missing a message id. A missing translation
+//! therefore never fails outright; it degrades to a guaranteed-present
+//! default.
+
+use std::{collections::HashMap, fs, path::Path};
+
+use crate::Error;
+
+/// A message bundle: `.ftl`-like `id = text` entries, one per line,
+/// with `{ $name }` placeholders resolved by [`Localizer::render`].
+#[derive(Debug, Clone, Default)]
+pub struct Bundle
+{
+    messages: HashMap<String, String>,
+}
+
+impl Bundle
+{
+    /// Parses a `.ftl`-like source. Blank lines and lines starting
+    /// with `#` are ignored; every other line must be `id = text`.
+    pub fn parse(source: &str) -> Self
+    {
+        let messages = source
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| line.split_once('='))
+            .map(|(id, text)| (id.trim().to_string(), text.trim().to_string()))
+            .collect();
+
+        Bundle { messages }
+    }
+
+    fn get(&self, id: &str) -> Option<&str>
+    {
+        self.messages.get(id).map(String::as_str)
+    }
+}
+
+/// The built-in English bundle, always consulted last. It defines every
+/// id that [`Localizer::render_error`] uses, so rendering an [`Error`]
+/// never falls back to the bare message id; [`Localizer::render`]
+/// returns the bare id only for ids absent from this bundle too.
+const DEFAULT_EN: &str = "\
+error-input = Could not read input: { $source }
+error-parsing = Could not parse \"{ $input }\" to number
+error-empty-input = Cannot compute { $op } of an empty stream
+error-overflow = Overflow while computing { $op }: combining { $accumulator } and { $addend } exceeds the range of the numeric type
+error-usage = { $message }
+";
+
+/// Resolves [`Error`] messages through an ordered list of requested
+/// locales, falling back through the chain and ultimately to the
+/// built-in English default.
+pub struct Localizer
+{
+    bundles: Vec<Bundle>,
+    default: Bundle,
+}
+
+impl Localizer
+{
+    /// Builds a localizer for `locales`, in order of preference, with
+    /// no bundles loaded -- every lookup falls through to English.
+    pub fn new(locales: impl IntoIterator<Item = String>) -> Self
+    {
+        Self::with_bundles(locales, |_locale| None)
+    }
+
+    /// Builds a localizer for `locales`, loading `<dir>/<locale>.ftl`
+    /// for each requested locale that has one.
+    pub fn with_bundle_dir(
+        locales: impl IntoIterator<Item = String>,
+        dir: impl AsRef<Path>,
+    ) -> Self
+    {
+        let dir = dir.as_ref();
+
+        Self::with_bundles(locales, |locale| {
+            fs::read_to_string(dir.join(format!("{}.ftl", locale)))
+                .ok()
+                .map(|source| Bundle::parse(&source))
+        })
+    }
+
+    fn with_bundles(
+        locales: impl IntoIterator<Item = String>,
+        mut load: impl FnMut(&str) -> Option<Bundle>,
+    ) -> Self
+    {
+        let bundles = locales
+            .into_iter()
+            .filter_map(|locale| load(&locale))
+            .collect();
+
+        Localizer {
+            bundles,
+            default: Bundle::parse(DEFAULT_EN),
+        }
+    }
+
+    /// Renders message `id` with `args`, walking the locale fallback
+    /// chain before falling back to the built-in default.
+    pub fn render(&self, id: &str, args: &[(&str, &str)]) -> String
+    {
+        let template = self
+            .bundles
+            .iter()
+            .find_map(|bundle| bundle.get(id))
+            .or_else(|| self.default.get(id))
+            .unwrap_or(id);
+
+        interpolate(template, args)
+    }
+
+    /// Renders `error` by message id, using the error's own data as
+    /// interpolation arguments.
+    pub fn render_error(&self, error: &Error) -> String
+    {
+        match error {
+            Error::InputError(source) => {
+                self.render("error-input", &[("source", &source.to_string())])
+            }
+            Error::ParsingError { input, .. } => {
+                self.render("error-parsing", &[("input", input)])
+            }
+            Error::EmptyInputError { op } => {
+                self.render("error-empty-input", &[("op", &format!("{:?}", op))])
+            }
+            Error::OverflowError { op, accumulator, addend } => self.render(
+                "error-overflow",
+                &[
+                    ("op", &format!("{:?}", op)),
+                    ("accumulator", accumulator),
+                    ("addend", addend),
+                ],
+            ),
+            Error::UsageError(message) => {
+                self.render("error-usage", &[("message", message)])
+            }
+        }
+    }
+}
+
+fn interpolate(template: &str, args: &[(&str, &str)]) -> String
+{
+    let mut rendered = template.to_string();
+
+    for (name, value) in args {
+        rendered = rendered
+            .replace(&format!("{{ ${} }}", name), value)
+            .replace(&format!("{{${}}}", name), value);
+    }
+
+    rendered
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn falls_back_to_builtin_default_when_no_bundle_is_loaded()
+    {
+        let localizer = Localizer::new(vec!["de-DE".to_string()]);
+
+        assert_eq!(
+            localizer.render("error-input", &[("source", "oops")]),
+            "Could not read input: oops"
+        );
+    }
+
+    #[test]
+    fn with_bundle_dir_loads_a_matching_locale_file()
+    {
+        let dir = std::env::temp_dir().join(format!(
+            "stdio-utils-l10n-test-{}-{}",
+            std::process::id(),
+            "loads-a-matching-locale-file"
+        ));
+        fs::create_dir_all(&dir).expect("Failed to create test bundle dir");
+        fs::write(
+            dir.join("de-DE.ftl"),
+            "error-input = Eingabe konnte nicht gelesen werden: { $source }\n",
+        )
+        .expect("Failed to write test bundle");
+
+        let localizer =
+            Localizer::with_bundle_dir(vec!["de-DE".to_string()], &dir);
+
+        assert_eq!(
+            localizer.render("error-input", &[("source", "oops")]),
+            "Eingabe konnte nicht gelesen werden: oops"
+        );
+
+        // Ids absent from the loaded bundle still fall through to the
+        // built-in English default.
+        assert_eq!(
+            localizer.render("error-usage", &[("message", "nope")]),
+            "nope"
+        );
+
+        fs::remove_dir_all(&dir).expect("Failed to clean up test bundle dir");
+    }
+
+    #[test]
+    fn with_bundle_dir_falls_back_when_no_file_matches_the_locale()
+    {
+        let dir = std::env::temp_dir().join(format!(
+            "stdio-utils-l10n-test-{}-{}",
+            std::process::id(),
+            "falls-back-when-no-file-matches"
+        ));
+        fs::create_dir_all(&dir).expect("Failed to create test bundle dir");
+
+        let localizer =
+            Localizer::with_bundle_dir(vec!["fr-FR".to_string()], &dir);
+
+        assert_eq!(
+            localizer.render("error-input", &[("source", "oops")]),
+            "Could not read input: oops"
+        );
+
+        fs::remove_dir_all(&dir).expect("Failed to clean up test bundle dir");
+    }
+
+    #[test]
+    fn falls_through_a_locale_missing_the_requested_id()
+    {
+        let first = Bundle::parse("error-usage = nur auf deutsch\n");
+        let second = Bundle::parse("error-input = nicht auf englisch\n");
+
+        let localizer = Localizer {
+            bundles: vec![first, second],
+            default: Bundle::parse(DEFAULT_EN),
+        };
+
+        // Neither loaded bundle defines "error-parsing", so this must
+        // degrade all the way to the built-in English default.
+        assert_eq!(
+            localizer.render("error-parsing", &[("input", "nope")]),
+            "Could not parse \"nope\" to number"
+        );
+    }
+
+    #[test]
+    fn interpolates_multiple_arguments()
+    {
+        let rendered = interpolate(
+            "{ $a } and { $b }",
+            &[("a", "first"), ("b", "second")],
+        );
+
+        assert_eq!(rendered, "first and second");
+    }
+
+    #[test]
+    fn render_error_renders_parsing_errors()
+    {
+        let localizer = Localizer::new(std::iter::empty());
+        let error = Error::ParsingError {
+            input:  "xyz".to_string(),
+            source: "xyz".parse::<i64>().unwrap_err(),
+        };
+
+        assert_eq!(
+            localizer.render_error(&error),
+            "Could not parse \"xyz\" to number"
+        );
+    }
+}
@@ -18,8 +18,8 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-//! A minimal library that sums numbers read from a stream of strings,
-//! such as `stdin`:
+//! A minimal library for reducing numbers read from a stream of
+//! strings, such as `stdin`. The common case is still summation:
 //!
 //!     let twenty = "20";
 //!     let twentytwo = "22";
@@ -30,10 +30,107 @@
 //!     let twentytwo = Ok("22");
 //!     let stream = vec![twenty, twentytwo].into_iter();
 //!     assert_eq!(stdio_utils::sum(stream).unwrap(), 42);
+//!
+//! [`reduce()`] generalizes this to any [`Operation`] (`Product`,
+//! `Min`, `Max`, `Mean`, `Count`), is generic over the numeric
+//! [`Num`] type (`i64` by default, `i128` for totals that would
+//! otherwise overflow), and reports overflow instead of wrapping.
+//! [`reduce_parallel()`]/[`sum_parallel()`] offer an opt-in
+//! multi-threaded path for very large streams, and the [`l10n`]
+//! module can localize the resulting [`Error`] messages.
 
 use std::{io, num};
 
-type Number = isize;
+pub mod l10n;
+pub mod sysexits;
+
+mod parallel;
+pub use parallel::{reduce_parallel, sum_parallel};
+
+/// The numeric type used by [`sum()`]/[`sum_strings()`] when the caller
+/// does not need to opt into a wider type via [`reduce()`].
+type Number = i64;
+
+/// Numeric types that [`reduce()`] can fold over.
+///
+/// Implemented for [`i64`] (the default, used by [`sum()`]/
+/// [`sum_strings()`]) and [`i128`] for callers who expect totals large
+/// enough to overflow 64 bits.
+pub trait Num:
+    Copy + PartialOrd + std::fmt::Display + std::str::FromStr<Err = num::ParseIntError>
+{
+    /// The additive identity, returned for an empty [`Operation::Sum`].
+    fn zero() -> Self;
+
+    /// The multiplicative identity, returned for an empty
+    /// [`Operation::Product`].
+    fn one() -> Self;
+
+    /// Converts a non-negative count, failing if it does not fit.
+    fn from_usize(n: usize) -> Option<Self>;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn checked_div(self, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! impl_num {
+    ($($t:ty),+ $(,)?) => {
+        $(
+            impl Num for $t
+            {
+                fn zero() -> Self { 0 }
+
+                fn one() -> Self { 1 }
+
+                fn from_usize(n: usize) -> Option<Self>
+                {
+                    <$t as std::convert::TryFrom<usize>>::try_from(n).ok()
+                }
+
+                fn checked_add(self, rhs: Self) -> Option<Self>
+                {
+                    <$t>::checked_add(self, rhs)
+                }
+
+                fn checked_mul(self, rhs: Self) -> Option<Self>
+                {
+                    <$t>::checked_mul(self, rhs)
+                }
+
+                fn checked_div(self, rhs: Self) -> Option<Self>
+                {
+                    <$t>::checked_div(self, rhs)
+                }
+            }
+        )+
+    };
+}
+
+impl_num!(i64, i128);
+
+/// Selects which reduction [`reduce()`] performs over a stream of numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation
+{
+    /// Adds the numbers, yielding `0` for an empty stream.
+    Sum,
+
+    /// Multiplies the numbers, yielding `1` for an empty stream.
+    Product,
+
+    /// Smallest number in the stream. Errors on an empty stream.
+    Min,
+
+    /// Largest number in the stream. Errors on an empty stream.
+    Max,
+
+    /// Arithmetic mean of the numbers, yielding `0` for an empty stream.
+    Mean,
+
+    /// Number of elements in the stream.
+    Count,
+}
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error
@@ -49,6 +146,57 @@ pub enum Error
         input:  String,
         source: num::ParseIntError,
     },
+
+    /// Returned when [`Operation::Min`] or [`Operation::Max`] is requested
+    /// over a stream that turns out to be empty.
+    #[error("Cannot compute {op:?} of an empty stream")]
+    EmptyInputError
+    {
+        op: Operation
+    },
+
+    /// Returned by callers, such as the CLI, that detect invalid usage
+    /// before ever touching the input stream, e.g. an unrecognized flag.
+    #[error("{0}")]
+    UsageError(String),
+
+    /// Returned when folding the stream would overflow the chosen
+    /// numeric type, e.g. when summing enough large values into an
+    /// `i64`. Carries the accumulator and offending addend so the
+    /// message stays descriptive across [`Num`] implementations.
+    #[error(
+        "Overflow while computing {op:?}: combining {accumulator} and \
+         {addend} exceeds the range of the numeric type"
+    )]
+    OverflowError
+    {
+        op:          Operation,
+        accumulator: String,
+        addend:      String,
+    },
+}
+
+impl Error
+{
+    /// Maps this error onto a `<sysexits.h>`-style process exit code,
+    /// so scripts invoking the CLI can act on *why* it failed.
+    pub fn exit_code(&self) -> i32
+    {
+        use sysexits::{EX_DATAERR, EX_IOERR, EX_NOINPUT, EX_USAGE};
+
+        match self {
+            Error::InputError(e) => match e.kind() {
+                io::ErrorKind::NotFound | io::ErrorKind::UnexpectedEof => {
+                    EX_NOINPUT
+                }
+                _ => EX_IOERR,
+            },
+            Error::ParsingError { .. } => EX_DATAERR,
+            Error::EmptyInputError { .. } => EX_DATAERR,
+            Error::OverflowError { .. } => EX_DATAERR,
+            Error::UsageError(_) => EX_USAGE,
+        }
+    }
 }
 
 /// Reads a stream of “stringy” values (`AsRef<str>`),
@@ -112,7 +260,143 @@ where
     S: AsRef<str>,
     T: Iterator<Item = Result<S, io::Error>>,
 {
-    read(lines).map(|line| as_number(line?)).sum()
+    reduce::<Number, _, _>(Operation::Sum, lines)
+}
+
+/// Reads a stream of `Result`s containing either
+/// “stringy” input (`AsRef<str>`) or I/O errors,
+/// parses the input as numbers,
+/// and folds them according to the chosen [`Operation`].
+///
+/// # Examples
+///
+///     use stdio_utils::Operation;
+///
+///     let stream = vec![Ok("20"), Ok("22")].into_iter();
+///     let result: i64 = stdio_utils::reduce(Operation::Max, stream).unwrap();
+///     assert_eq!(result, 22);
+///
+/// # Errors
+///
+/// Parsing and I/O errors are propagated as with [`sum()`]. In addition,
+/// [`Operation::Min`] and [`Operation::Max`] return
+/// [`Error::EmptyInputError`] when the stream is empty.
+///
+/// # See also
+///
+/// - [`sum()`](fn.sum.html): Shorthand for `reduce(Operation::Sum, _)`
+
+pub fn reduce<N, S, T>(op: Operation, lines: T) -> Result<N, Error>
+where
+    N: Num,
+    S: AsRef<str>,
+    T: Iterator<Item = Result<S, io::Error>>,
+{
+    let mut numbers = read(lines).map(|line| as_number::<N>(line?));
+
+    match op {
+        Operation::Sum => fold_checked(op, &mut numbers, N::zero(), N::checked_add),
+        Operation::Product => fold_checked(op, &mut numbers, N::one(), N::checked_mul),
+        Operation::Count => count(op, &mut numbers),
+        Operation::Min => fold_non_empty(op, &mut numbers, |a, b| if a < b { a } else { b }),
+        Operation::Max => fold_non_empty(op, &mut numbers, |a, b| if a > b { a } else { b }),
+        Operation::Mean => mean(op, &mut numbers),
+    }
+}
+
+/// Folds `numbers` with `f`, reporting an [`Error::OverflowError`]
+/// (rather than silently wrapping) the moment `f` returns `None`.
+pub(crate) fn fold_checked<N, T>(
+    op: Operation,
+    numbers: &mut T,
+    init: N,
+    f: impl Fn(N, N) -> Option<N>,
+) -> Result<N, Error>
+where
+    N: Num,
+    T: Iterator<Item = Result<N, Error>>,
+{
+    numbers.try_fold(init, |acc, n| {
+        let n = n?;
+
+        f(acc, n).ok_or_else(|| Error::OverflowError {
+            op,
+            accumulator: acc.to_string(),
+            addend: n.to_string(),
+        })
+    })
+}
+
+pub(crate) fn fold_non_empty<N, T>(
+    op: Operation,
+    numbers: &mut T,
+    f: impl Fn(N, N) -> N,
+) -> Result<N, Error>
+where
+    N: Num,
+    T: Iterator<Item = Result<N, Error>>,
+{
+    let first = match numbers.next() {
+        Some(n) => n?,
+        None => return Err(Error::EmptyInputError { op }),
+    };
+
+    numbers.try_fold(first, |acc, n| n.map(|n| f(acc, n)))
+}
+
+fn count<N, T>(op: Operation, numbers: &mut T) -> Result<N, Error>
+where
+    N: Num,
+    T: Iterator<Item = Result<N, Error>>,
+{
+    let mut seen: usize = 0;
+
+    for n in numbers {
+        n?;
+        seen += 1;
+    }
+
+    N::from_usize(seen).ok_or_else(|| Error::OverflowError {
+        op,
+        accumulator: seen.to_string(),
+        addend: 1.to_string(),
+    })
+}
+
+fn mean<N, T>(op: Operation, numbers: &mut T) -> Result<N, Error>
+where
+    N: Num,
+    T: Iterator<Item = Result<N, Error>>,
+{
+    let mut total = N::zero();
+    let mut seen: usize = 0;
+
+    for n in numbers {
+        let n = n?;
+
+        total = total.checked_add(n).ok_or_else(|| Error::OverflowError {
+            op,
+            accumulator: total.to_string(),
+            addend: n.to_string(),
+        })?;
+        seen += 1;
+    }
+
+    if seen == 0 {
+        return Ok(N::zero());
+    }
+
+    let count = N::from_usize(seen).ok_or_else(|| Error::OverflowError {
+        op,
+        accumulator: total.to_string(),
+        addend: seen.to_string(),
+    })?;
+
+    total.checked_div(count).ok_or_else(|| Error::OverflowError {
+        op,
+        accumulator: total.to_string(),
+        addend: count.to_string(),
+    })
 }
 
 fn read<S, T>(lines: T) -> impl Iterator<Item = Result<S, Error>>
@@ -123,7 +407,7 @@ where
     lines.map(|line| line.map_err(Error::InputError))
 }
 
-fn as_number(line: impl AsRef<str>) -> Result<Number, Error>
+pub(crate) fn as_number<N: Num>(line: impl AsRef<str>) -> Result<N, Error>
 {
     // We cannot use From here because ParseIntError
     // does not contain a reference to offending input.
@@ -144,19 +428,19 @@ mod tests
     #[test]
     fn parses_a_number()
     {
-        assert_eq!(as_number("42").unwrap(), 42);
+        assert_eq!(as_number::<Number>("42").unwrap(), 42);
     }
 
     #[test]
     fn parses_a_number_with_whitespace()
     {
-        assert_eq!(as_number("\t 42\n").unwrap(), 42);
+        assert_eq!(as_number::<Number>("\t 42\n").unwrap(), 42);
     }
 
     #[test]
     fn fails_on_invalid_character()
     {
-        let result = as_number(bad_input_char());
+        let result = as_number::<Number>(bad_input_char());
         let msg = result.unwrap_err().to_string();
         assert!(
             msg.contains(bad_input_char()),
@@ -168,7 +452,7 @@ mod tests
     #[test]
     fn fails_on_empty_input()
     {
-        let msg = as_number("").unwrap_err().to_string();
+        let msg = as_number::<Number>("").unwrap_err().to_string();
         assert!(
             !msg.contains(bad_input_char()),
             "Unexpected (hardcoded?) text in error message \"{}\"",
@@ -191,6 +475,93 @@ mod tests
         assert_eq!(sum_strings(stream).unwrap(), 42);
     }
 
+    #[test]
+    fn reduce_computes_product()
+    {
+        let stream = vec![Ok("2"), Ok("3"), Ok("7")].into_iter();
+
+        assert_eq!(reduce::<Number, _, _>(Operation::Product, stream).unwrap(), 42);
+    }
+
+    #[test]
+    fn reduce_computes_mean()
+    {
+        let stream = vec![Ok("10"), Ok("20"), Ok("30")].into_iter();
+
+        assert_eq!(reduce::<Number, _, _>(Operation::Mean, stream).unwrap(), 20);
+    }
+
+    #[test]
+    fn reduce_computes_count()
+    {
+        let stream = vec![Ok("10"), Ok("20"), Ok("30")].into_iter();
+
+        assert_eq!(reduce::<Number, _, _>(Operation::Count, stream).unwrap(), 3);
+    }
+
+    #[test]
+    fn reduce_min_and_max_error_on_empty_stream()
+    {
+        let empty: Vec<Result<&str, io::Error>> = vec![];
+
+        assert!(reduce::<Number, _, _>(Operation::Min, empty.into_iter()).is_err());
+
+        let empty: Vec<Result<&str, io::Error>> = vec![];
+
+        assert!(reduce::<Number, _, _>(Operation::Max, empty.into_iter()).is_err());
+    }
+
+    #[test]
+    fn reduce_sum_and_mean_default_to_zero_on_empty_stream()
+    {
+        let empty: Vec<Result<&str, io::Error>> = vec![];
+        assert_eq!(reduce::<Number, _, _>(Operation::Sum, empty.into_iter()).unwrap(), 0);
+
+        let empty: Vec<Result<&str, io::Error>> = vec![];
+        assert_eq!(reduce::<Number, _, _>(Operation::Mean, empty.into_iter()).unwrap(), 0);
+    }
+
+    #[test]
+    fn parsing_error_maps_to_ex_dataerr()
+    {
+        let err = as_number::<Number>("not_a_number").unwrap_err();
+        assert_eq!(err.exit_code(), sysexits::EX_DATAERR);
+    }
+
+    #[test]
+    fn usage_error_maps_to_ex_usage()
+    {
+        let err = Error::UsageError("bad flag".to_string());
+        assert_eq!(err.exit_code(), sysexits::EX_USAGE);
+    }
+
+    #[test]
+    fn not_found_input_error_maps_to_ex_noinput()
+    {
+        let source = io::Error::from(io::ErrorKind::NotFound);
+        let err = Error::InputError(source);
+        assert_eq!(err.exit_code(), sysexits::EX_NOINPUT);
+    }
+
+    #[test]
+    fn sum_reports_overflow_instead_of_wrapping()
+    {
+        let near_max = (i64::MAX - 1).to_string();
+        let stream = vec![Ok(near_max.as_str()), Ok("2")].into_iter();
+
+        let err = reduce::<i64, _, _>(Operation::Sum, stream).unwrap_err();
+        assert!(matches!(err, Error::OverflowError { .. }));
+    }
+
+    #[test]
+    fn i128_has_enough_headroom_for_totals_that_overflow_i64()
+    {
+        let near_max = (i64::MAX as i128).to_string();
+        let stream = vec![Ok(near_max.as_str()), Ok("2")].into_iter();
+
+        assert!(reduce::<i128, _, _>(Operation::Sum, stream).is_ok());
+    }
+
     fn bad_input_char() -> &'static str
     {
         "$"
@@ -1,20 +1,115 @@
 use std::io::BufRead;
-use stdio_utils::ApplicationError;
+use stdio_utils::{l10n::Localizer, Error, Operation};
+
+/// Parsed command line: which [`Operation`] to run, and how many
+/// worker threads to run it with.
+struct Config {
+    op:      Operation,
+    threads: usize,
+}
 
 fn main() {
+    // STDIO_UTILS_L10N_DIR points at a directory of `<locale>.ftl`
+    // bundles; without it we fall straight through to the built-in
+    // English default.
+    let locales = requested_locales();
+    let localizer = match std::env::var("STDIO_UTILS_L10N_DIR") {
+        Ok(dir) => Localizer::with_bundle_dir(locales, dir),
+        Err(_) => Localizer::new(locales),
+    };
+
+    let config = parse_args(std::env::args()).unwrap_or_else(|err| {
+        eprintln!("stdio-utils: {}", localizer.render_error(&err));
+        eprintln!(
+            "usage: stdio-utils [-s | -p | -m | -o sum|product|min|max|mean|count] \
+             [-j N | --threads N] [--]"
+        );
+        std::process::exit(err.exit_code())
+    });
+
     let stdin  = std::io::stdin();
-    let result = stdio_utils::sum(stdin.lock().lines());
+    let result: Result<i64, Error> = if config.threads > 1 {
+        // Large-input path: read the whole buffer once and fold it
+        // across `config.threads` worker threads.
+        stdio_utils::reduce_parallel(config.op, stdin.lock(), config.threads)
+    } else {
+        stdio_utils::reduce(config.op, stdin.lock().lines())
+    };
 
-    let sum = result.unwrap_or_else(|err| match err {
-        ApplicationError::InputError(e) => {
-            eprintln!("I/O error reading from stdin: {}", e);
-            std::process::exit(1)
-        }
-        ApplicationError::ParsingError(e) => {
-            eprintln!("Bad input data: {}", e);
-            std::process::exit(1)
-        },
+    let value = result.unwrap_or_else(|err| {
+        eprintln!("stdio-utils: {}", localizer.render_error(&err));
+        std::process::exit(err.exit_code())
     });
 
-    println!("{}", sum);
+    println!("{}", value);
+}
+
+/// Builds the requested-locale chain from `LC_MESSAGES`, falling back
+/// to `LANG`, trimming off any `.encoding` suffix (e.g. `de_DE.UTF-8`).
+/// `C`/`POSIX` are dropped since they carry no translation.
+fn requested_locales() -> Vec<String> {
+    ["LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .map(|locale| locale.split('.').next().unwrap_or(&locale).to_string())
+        .filter(|locale| !locale.is_empty() && locale != "C" && locale != "POSIX")
+        .collect()
+}
+
+/// A minimal POSIX `getopt()`-style scan of `std::env::args()`: recognizes
+/// `-o <op>`, the shorthands `-s`/`-p`/`-m` (sum, product, mean), and
+/// `-j <n>`/`--threads <n>` to opt into the parallel reduction path.
+/// Each flag must be its own argument: clusters such as `-sp` are not
+/// split and are reported as unknown. Stops option parsing at a bare
+/// `--` and reports unknown flags as a usage error.
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Config, Error> {
+    let mut op      = Operation::Sum;
+    let mut threads = 1;
+    let mut args    = args.skip(1);
+
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            break;
+        }
+
+        match arg.as_str() {
+            "-s" => op = Operation::Sum,
+            "-p" => op = Operation::Product,
+            "-m" => op = Operation::Mean,
+            "-o" => {
+                let value = args.next().ok_or_else(|| {
+                    Error::UsageError("option -o requires an argument".to_string())
+                })?;
+
+                op = parse_operation_name(&value)?;
+            }
+            "-j" | "--threads" => {
+                let value = args.next().ok_or_else(|| {
+                    Error::UsageError(format!("option {} requires an argument", arg))
+                })?;
+
+                threads = value.parse::<usize>().ok().filter(|n| *n > 0).ok_or_else(|| {
+                    Error::UsageError(format!("invalid thread count: \"{}\"", value))
+                })?;
+            }
+            other if other.starts_with('-') && other.len() > 1 => {
+                return Err(Error::UsageError(format!("unknown option: {}", other)));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(Config { op, threads })
+}
+
+fn parse_operation_name(name: &str) -> Result<Operation, Error> {
+    match name {
+        "sum" => Ok(Operation::Sum),
+        "product" => Ok(Operation::Product),
+        "min" => Ok(Operation::Min),
+        "max" => Ok(Operation::Max),
+        "mean" => Ok(Operation::Mean),
+        "count" => Ok(Operation::Count),
+        other => Err(Error::UsageError(format!("unknown operation: {}", other))),
+    }
 }
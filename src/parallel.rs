@@ -0,0 +1,401 @@
+// Copyright 2020 Andreas Waidler
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included
+// in all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! A multi-threaded counterpart to [`crate::reduce()`] for the huge
+//! inputs the benchmark suite cares about: the input is read into
+//! memory once, split into line-aligned byte ranges, and each range is
+//! folded by its own worker thread before the partial results are
+//! combined with the operation's associative merge. Splitting on raw
+//! byte buffers (rather than reusing `BufRead::lines()`) avoids
+//! allocating a `String` per line.
+
+use std::io::BufRead;
+
+use crate::{as_number, fold_checked, fold_non_empty, Error, Num, Operation};
+
+/// Shorthand for [`reduce_parallel()`] with [`Operation::Sum`].
+pub fn sum_parallel<R: BufRead>(reader: R, threads: usize) -> Result<i64, Error>
+{
+    reduce_parallel(Operation::Sum, reader, threads)
+}
+
+/// Reads all of `reader`, splits it into up to `threads` line-aligned
+/// chunks, folds each chunk on its own worker thread, and combines the
+/// partial results with `op`'s associative merge (for [`Operation::Mean`],
+/// partial `(total, count)` pairs are combined and only divided once,
+/// at the end).
+///
+/// # Errors
+///
+/// If more than one chunk fails to parse, the error reported is the
+/// one at the lowest byte offset in the original input -- not
+/// whichever worker thread happens to finish first -- so the result is
+/// deterministic regardless of thread scheduling.
+pub fn reduce_parallel<N, R>(
+    op: Operation,
+    mut reader: R,
+    threads: usize,
+) -> Result<N, Error>
+where
+    N: Num + Send,
+    R: BufRead,
+{
+    let mut buf = Vec::new();
+    reader
+        .read_to_end(&mut buf)
+        .map_err(Error::InputError)?;
+
+    let chunks = split_into_chunks(&buf, threads.max(1));
+
+    let mut results: Vec<Result<Partial<N>, (usize, Error)>> =
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = chunks
+                .iter()
+                .map(|&(offset, chunk)| {
+                    scope.spawn(move || process_chunk::<N>(op, offset, chunk))
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("worker thread panicked"))
+                .collect()
+        });
+
+    let earliest_failure = results
+        .iter()
+        .filter_map(|result| result.as_ref().err().map(|(offset, _)| *offset))
+        .min();
+
+    if let Some(offset) = earliest_failure {
+        let index = results
+            .iter()
+            .position(|result| matches!(result, Err((o, _)) if *o == offset))
+            .expect("earliest_failure came from one of these results");
+
+        return match results.remove(index) {
+            Err((_, err)) => Err(err),
+            Ok(_) => unreachable!("index was located via an Err(_) match above"),
+        };
+    }
+
+    let partials = results
+        .into_iter()
+        .map(|result| match result {
+            Ok(partial) => partial,
+            Err(_) => unreachable!("earliest_failure is None, so no result is Err"),
+        })
+        .collect();
+
+    merge_partials(op, partials)
+}
+
+/// A single chunk's contribution to the final result, in a shape that
+/// can be combined with other chunks' contributions independently of
+/// how many chunks there were.
+enum Partial<N>
+{
+    /// [`Operation::Sum`], [`Operation::Product`] and [`Operation::Count`]:
+    /// a single foldable value.
+    Value(N),
+
+    /// [`Operation::Min`] and [`Operation::Max`]: `None` if the chunk
+    /// contained no numbers.
+    MinMax(Option<N>),
+
+    /// [`Operation::Mean`]: a running `(total, count)` pair, divided
+    /// only once all chunks have been combined.
+    MeanParts(N, N),
+}
+
+/// Parses and folds one chunk in isolation. `offset` is the chunk's
+/// starting byte position in the original input, used to make error
+/// reporting position-deterministic in [`reduce_parallel()`].
+fn process_chunk<N: Num>(
+    op: Operation,
+    offset: usize,
+    chunk: &[u8],
+) -> Result<Partial<N>, (usize, Error)>
+{
+    let text = std::str::from_utf8(chunk).map_err(|e| {
+        let position = offset + e.valid_up_to();
+        let source = std::io::Error::new(std::io::ErrorKind::InvalidData, e);
+
+        (position, Error::InputError(source))
+    })?;
+
+    let mut position = offset;
+    let mut numbers = Vec::new();
+
+    for line in text.split('\n') {
+        if !line.is_empty() {
+            let n: N = as_number(line).map_err(|err| (position, err))?;
+            numbers.push(n);
+        }
+
+        position += line.len() + 1;
+    }
+
+    partial_for(op, &numbers).map_err(|err| (offset, err))
+}
+
+fn partial_for<N: Num>(op: Operation, numbers: &[N]) -> Result<Partial<N>, Error>
+{
+    match op {
+        Operation::Sum => {
+            fold_checked(op, &mut numbers.iter().copied().map(Ok), N::zero(), N::checked_add)
+                .map(Partial::Value)
+        }
+        Operation::Product => {
+            fold_checked(op, &mut numbers.iter().copied().map(Ok), N::one(), N::checked_mul)
+                .map(Partial::Value)
+        }
+        Operation::Count => {
+            N::from_usize(numbers.len())
+                .map(Partial::Value)
+                .ok_or_else(|| Error::OverflowError {
+                    op,
+                    accumulator: numbers.len().to_string(),
+                    addend: 1.to_string(),
+                })
+        }
+        Operation::Min => Ok(Partial::MinMax(
+            fold_non_empty(op, &mut numbers.iter().copied().map(Ok), |a, b| {
+                if a < b { a } else { b }
+            })
+            .ok(),
+        )),
+        Operation::Max => Ok(Partial::MinMax(
+            fold_non_empty(op, &mut numbers.iter().copied().map(Ok), |a, b| {
+                if a > b { a } else { b }
+            })
+            .ok(),
+        )),
+        Operation::Mean => {
+            let total = fold_checked(
+                op,
+                &mut numbers.iter().copied().map(Ok),
+                N::zero(),
+                N::checked_add,
+            )?;
+            let count =
+                N::from_usize(numbers.len()).ok_or_else(|| Error::OverflowError {
+                    op,
+                    accumulator: total.to_string(),
+                    addend: numbers.len().to_string(),
+                })?;
+
+            Ok(Partial::MeanParts(total, count))
+        }
+    }
+}
+
+fn merge_partials<N: Num>(op: Operation, partials: Vec<Partial<N>>) -> Result<N, Error>
+{
+    match op {
+        Operation::Sum | Operation::Count => {
+            merge_values(op, partials, N::zero(), N::checked_add)
+        }
+        Operation::Product => merge_values(op, partials, N::one(), N::checked_mul),
+        Operation::Min => merge_min_max(op, partials, |a, b| if a < b { a } else { b }),
+        Operation::Max => merge_min_max(op, partials, |a, b| if a > b { a } else { b }),
+        Operation::Mean => {
+            let (total, count) = partials.into_iter().try_fold(
+                (N::zero(), N::zero()),
+                |(total, count), partial| {
+                    let (t, c) = match partial {
+                        Partial::MeanParts(t, c) => (t, c),
+                        _ => unreachable!("Operation::Mean only produces MeanParts"),
+                    };
+
+                    let total =
+                        total.checked_add(t).ok_or_else(|| Error::OverflowError {
+                            op,
+                            accumulator: total.to_string(),
+                            addend: t.to_string(),
+                        })?;
+                    let count =
+                        count.checked_add(c).ok_or_else(|| Error::OverflowError {
+                            op,
+                            accumulator: count.to_string(),
+                            addend: c.to_string(),
+                        })?;
+
+                    Ok((total, count))
+                },
+            )?;
+
+            if count == N::zero() {
+                return Ok(N::zero());
+            }
+
+            total.checked_div(count).ok_or_else(|| Error::OverflowError {
+                op,
+                accumulator: total.to_string(),
+                addend: count.to_string(),
+            })
+        }
+    }
+}
+
+fn merge_values<N: Num>(
+    op: Operation,
+    partials: Vec<Partial<N>>,
+    init: N,
+    f: impl Fn(N, N) -> Option<N>,
+) -> Result<N, Error>
+{
+    partials.into_iter().try_fold(init, |acc, partial| {
+        let v = match partial {
+            Partial::Value(v) => v,
+            _ => unreachable!("this operation only produces Partial::Value"),
+        };
+
+        f(acc, v).ok_or_else(|| Error::OverflowError {
+            op,
+            accumulator: acc.to_string(),
+            addend: v.to_string(),
+        })
+    })
+}
+
+fn merge_min_max<N: Num>(
+    op: Operation,
+    partials: Vec<Partial<N>>,
+    f: impl Fn(N, N) -> N,
+) -> Result<N, Error>
+{
+    let merged = partials.into_iter().fold(None, |acc, partial| {
+        let v = match partial {
+            Partial::MinMax(v) => v,
+            _ => unreachable!("this operation only produces Partial::MinMax"),
+        };
+
+        match (acc, v) {
+            (Some(a), Some(b)) => Some(f(a, b)),
+            (Some(a), None) => Some(a),
+            (None, b) => b,
+        }
+    });
+
+    merged.ok_or(Error::EmptyInputError { op })
+}
+
+/// Splits `buf` into up to `parts` byte ranges, each ending right
+/// after a newline (the last range instead runs to the end of `buf`),
+/// so no chunk ever contains a partial line.
+fn split_into_chunks(buf: &[u8], parts: usize) -> Vec<(usize, &[u8])>
+{
+    if buf.is_empty() || parts <= 1 {
+        return vec![(0, buf)];
+    }
+
+    let target = buf.len() / parts;
+    let mut chunks = Vec::with_capacity(parts);
+    let mut start = 0;
+
+    for i in 0..parts {
+        if start >= buf.len() {
+            break;
+        }
+
+        let end = if i == parts - 1 {
+            buf.len()
+        } else {
+            let tentative = ((i + 1) * target).min(buf.len());
+
+            match buf[tentative..].iter().position(|&b| b == b'\n') {
+                Some(offset) => tentative + offset + 1,
+                None => buf.len(),
+            }
+        };
+
+        chunks.push((start, &buf[start..end]));
+        start = end;
+    }
+
+    chunks
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn sums_across_multiple_threads()
+    {
+        let input = (1..=100).map(|n| n.to_string()).collect::<Vec<_>>().join("\n");
+
+        let total = sum_parallel(input.as_bytes(), 4).unwrap();
+        assert_eq!(total, 5050);
+    }
+
+    #[test]
+    fn single_thread_matches_sequential_sum()
+    {
+        let input = "1\n2\n3\n4\n5\n";
+
+        assert_eq!(sum_parallel(input.as_bytes(), 1).unwrap(), 15);
+    }
+
+    #[test]
+    fn reduce_parallel_computes_mean_across_chunks()
+    {
+        let input = "10\n20\n30\n40\n";
+
+        let mean: i64 =
+            reduce_parallel(Operation::Mean, input.as_bytes(), 3).unwrap();
+        assert_eq!(mean, 25);
+    }
+
+    #[test]
+    fn reduce_parallel_computes_min_and_max_across_chunks()
+    {
+        let input = "5\n1\n9\n3\n7\n";
+
+        let min: i64 = reduce_parallel(Operation::Min, input.as_bytes(), 3).unwrap();
+        let max: i64 = reduce_parallel(Operation::Max, input.as_bytes(), 3).unwrap();
+
+        assert_eq!(min, 1);
+        assert_eq!(max, 9);
+    }
+
+    #[test]
+    fn reports_the_earliest_parse_error_deterministically()
+    {
+        let input = "1\nbad\n3\nalso_bad\n5\n";
+
+        let err: Error = reduce_parallel::<i64, _>(Operation::Sum, input.as_bytes(), 4)
+            .unwrap_err();
+
+        match err {
+            Error::ParsingError { input, .. } => assert_eq!(input, "bad"),
+            other => panic!("expected a parsing error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn empty_input_sums_to_zero()
+    {
+        let empty: &[u8] = b"";
+        assert_eq!(sum_parallel(empty, 4).unwrap(), 0);
+    }
+}
@@ -0,0 +1,36 @@
+// Copyright 2020 Andreas Waidler
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included
+// in all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Conventional BSD `<sysexits.h>` process exit codes.
+//!
+//! These let scripts act on *why* `stdio-utils` failed instead of just
+//! observing a blanket failure. See [`crate::Error::exit_code()`].
+
+/// The command was used incorrectly, e.g. with a bad flag or argument count.
+pub const EX_USAGE: i32 = 64;
+
+/// The input data was incorrect in some way.
+pub const EX_DATAERR: i32 = 65;
+
+/// An input file (not a system file) did not exist or was not readable.
+pub const EX_NOINPUT: i32 = 66;
+
+/// An error occurred while doing I/O on some file.
+pub const EX_IOERR: i32 = 74;